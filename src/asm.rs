@@ -0,0 +1,493 @@
+//! A human-readable text form of [`ByteCode`]/[`Program`], for writing
+//! tests and inspecting compiled output without hand-building [`crate::Bytes`]
+//! tuples. [`disassemble`] and [`assemble`] round-trip any [`Program`].
+
+use crate::{
+    constant::Constant, memory::Width, BinaryOperation, ByteCode, Locator, Program, Register,
+    UnaryOperation,
+};
+use std::{collections::HashMap, error::Error, fmt::Display};
+
+fn mnemonic_locator(addr: Locator) -> String {
+    match addr {
+        Locator::Address(addr) => format!("@0x{addr:x}"),
+        Locator::FromRegister(reg) => format!("r{reg}"),
+    }
+}
+fn mnemonic_binary(op: BinaryOperation) -> &'static str {
+    match op {
+        BinaryOperation::Add => "add",
+        BinaryOperation::Sub => "sub",
+        BinaryOperation::Div => "div",
+        BinaryOperation::Mul => "mul",
+        BinaryOperation::Mod => "mod",
+        BinaryOperation::Eq => "eq",
+        BinaryOperation::Ne => "ne",
+        BinaryOperation::Lt => "lt",
+        BinaryOperation::Le => "le",
+        BinaryOperation::Gt => "gt",
+        BinaryOperation::Ge => "ge",
+        BinaryOperation::And => "and",
+        BinaryOperation::Or => "or",
+        BinaryOperation::BitAnd => "band",
+        BinaryOperation::BitOr => "bor",
+        BinaryOperation::BitXor => "bxor",
+        BinaryOperation::Shl => "shl",
+        BinaryOperation::Shr => "shr",
+    }
+}
+fn mnemonic_unary(op: UnaryOperation) -> &'static str {
+    match op {
+        UnaryOperation::Neg => "neg",
+        UnaryOperation::Not => "not",
+        UnaryOperation::BitNot => "bnot",
+    }
+}
+
+fn format_constant(constant: &Constant) -> String {
+    match constant {
+        Constant::Unit => "unit".to_string(),
+        Constant::Bool(value) => format!("bool {value}"),
+        Constant::I8(value) => format!("i8 {value}"),
+        Constant::I16(value) => format!("i16 {value}"),
+        Constant::I32(value) => format!("i32 {value}"),
+        Constant::I64(value) => format!("i64 {value}"),
+        Constant::U8(value) => format!("u8 {value}"),
+        Constant::U16(value) => format!("u16 {value}"),
+        Constant::U32(value) => format!("u32 {value}"),
+        Constant::U64(value) => format!("u64 {value}"),
+        Constant::Float(value) => format!("float {value}"),
+        Constant::Text(value) => format!("text {value:?}"),
+        Constant::Bytes(value) => format!("bytes {value:?}"),
+        Constant::List(items) => {
+            let items = items.iter().map(format_constant).collect::<Vec<_>>().join(", ");
+            format!("list [{items}]")
+        }
+        Constant::Record(entries) => {
+            let entries = entries
+                .iter()
+                .map(|(key, value)| format!("{key:?}: {}", format_constant(value)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("record {{{entries}}}")
+        }
+    }
+}
+
+/// Render a [`Program`] as assembly text: a constant-pool header followed by
+/// one mnemonic line per [`ByteCode`].
+pub fn disassemble(program: &Program) -> String {
+    let mut out = String::new();
+    for (index, constant) in program.constants.iter().enumerate() {
+        out.push_str(&format!(".const #{index} {}\n", format_constant(constant)));
+    }
+    for bytecode in &program.code {
+        out.push_str(&match *bytecode {
+            ByteCode::None => "none".to_string(),
+            ByteCode::Halt => "halt".to_string(),
+            ByteCode::Jump { addr } => format!("jmp {}", mnemonic_locator(addr)),
+            ByteCode::JumpIf { cond, addr } => {
+                format!("jmpif r{cond}, {}", mnemonic_locator(addr))
+            }
+            ByteCode::String { dst, addr } => format!("str r{dst}, #{addr}"),
+            ByteCode::Int { dst, value } => format!("int r{dst}, {value}"),
+            ByteCode::Float { dst, value } => format!("float r{dst}, {value}"),
+            ByteCode::Bool { dst, value } => format!("bool r{dst}, {value}"),
+            ByteCode::Move { dst, src } => format!("move r{dst}, r{src}"),
+            ByteCode::Field { dst, src, field } => format!("field r{dst}, r{src}, {field}"),
+            ByteCode::Call { addr, args, dst } => {
+                format!("call r{dst}, {}, {args}", mnemonic_locator(addr))
+            }
+            ByteCode::Ecall { id, args, dst } => format!("ecall r{dst}, {id}, r{args}"),
+            ByteCode::Trap { code } => format!("trap {code}"),
+            ByteCode::Load { dst, addr, size } => {
+                format!("load.{} r{dst}, {}", u32::from(size), mnemonic_locator(addr))
+            }
+            ByteCode::Store { src, addr, size } => {
+                format!("store.{} {}, r{src}", u32::from(size), mnemonic_locator(addr))
+            }
+            ByteCode::Const { dst, index } => format!("const r{dst}, #{index}"),
+            ByteCode::Tick { dst } => format!("tick r{dst}"),
+            ByteCode::Binary { op, dst, left, right } => {
+                format!("{} r{dst}, r{left}, r{right}", mnemonic_binary(op))
+            }
+            ByteCode::Unary { op, dst, right } => {
+                format!("{} r{dst}, r{right}", mnemonic_unary(op))
+            }
+        });
+        out.push('\n');
+    }
+    out
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AsmError {
+    UnknownMnemonic(String),
+    MissingOperand(String),
+    InvalidOperand(String),
+    UnknownLabel(String),
+}
+impl Display for AsmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AsmError::UnknownMnemonic(m) => write!(f, "unknown mnemonic {m:?}"),
+            AsmError::MissingOperand(line) => write!(f, "missing operand in {line:?}"),
+            AsmError::InvalidOperand(operand) => write!(f, "invalid operand {operand:?}"),
+            AsmError::UnknownLabel(label) => write!(f, "unknown label {label:?}"),
+        }
+    }
+}
+impl Error for AsmError {}
+
+fn parse_register(operand: &str) -> Result<Register, AsmError> {
+    operand
+        .strip_prefix('r')
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| AsmError::InvalidOperand(operand.to_string()))
+}
+fn parse_u32(operand: &str) -> Result<u32, AsmError> {
+    operand
+        .parse()
+        .map_err(|_| AsmError::InvalidOperand(operand.to_string()))
+}
+fn parse_width(mnemonic: &str, line: &str) -> Result<Width, AsmError> {
+    let (_, width) = mnemonic
+        .split_once('.')
+        .ok_or_else(|| AsmError::InvalidOperand(line.to_string()))?;
+    parse_u32(width)
+        .ok()
+        .and_then(|width| Width::try_from(width).ok())
+        .ok_or_else(|| AsmError::InvalidOperand(line.to_string()))
+}
+/// Parse one [`Constant`] literal from the front of `input`, returning it
+/// along with whatever text follows. Used both for `.const` header lines
+/// and recursively for `list`/`record` elements.
+fn parse_constant(input: &str) -> Result<(Constant, &str), AsmError> {
+    let input = input.trim_start();
+    // Find the keyword without consuming whatever delimiter follows it — a
+    // no-argument keyword like `unit` may be immediately followed by the
+    // `,`/`]`/`}` that a caller further up (a `list`/`record` loop) still
+    // needs to see in order to detect the end of its own sequence.
+    let (word, rest) = match input
+        .find(|c: char| c.is_whitespace() || c == '[' || c == '{' || c == ',' || c == ']' || c == '}')
+    {
+        Some(index) => (&input[..index], &input[index..]),
+        None => (input, ""),
+    };
+    let rest_with_delim = &input[word.len()..];
+    let invalid = || AsmError::InvalidOperand(input.to_string());
+
+    macro_rules! scalar {
+        ($rest:expr) => {{
+            let rest = $rest.trim_start();
+            match rest.find(|c: char| c == ',' || c == ']' || c == '}') {
+                Some(index) => (rest[..index].trim(), &rest[index..]),
+                None => (rest.trim_end(), ""),
+            }
+        }};
+    }
+
+    match word {
+        "unit" => Ok((Constant::Unit, rest)),
+        "bool" => {
+            let (token, rest) = scalar!(rest);
+            Ok((Constant::Bool(token.parse().map_err(|_| invalid())?), rest))
+        }
+        "i8" => { let (t, r) = scalar!(rest); Ok((Constant::I8(t.parse().map_err(|_| invalid())?), r)) }
+        "i16" => { let (t, r) = scalar!(rest); Ok((Constant::I16(t.parse().map_err(|_| invalid())?), r)) }
+        "i32" => { let (t, r) = scalar!(rest); Ok((Constant::I32(t.parse().map_err(|_| invalid())?), r)) }
+        "i64" => { let (t, r) = scalar!(rest); Ok((Constant::I64(t.parse().map_err(|_| invalid())?), r)) }
+        "u8" => { let (t, r) = scalar!(rest); Ok((Constant::U8(t.parse().map_err(|_| invalid())?), r)) }
+        "u16" => { let (t, r) = scalar!(rest); Ok((Constant::U16(t.parse().map_err(|_| invalid())?), r)) }
+        "u32" => { let (t, r) = scalar!(rest); Ok((Constant::U32(t.parse().map_err(|_| invalid())?), r)) }
+        "u64" => { let (t, r) = scalar!(rest); Ok((Constant::U64(t.parse().map_err(|_| invalid())?), r)) }
+        "float" => { let (t, r) = scalar!(rest); Ok((Constant::Float(t.parse().map_err(|_| invalid())?), r)) }
+        "text" => {
+            let (text, rest) = parse_quoted(rest.trim_start())?;
+            Ok((Constant::Text(text), rest))
+        }
+        "bytes" => {
+            let rest = rest_with_delim.trim_start();
+            let rest = rest.strip_prefix('[').ok_or_else(invalid)?;
+            let (list, rest) = rest.split_once(']').ok_or_else(invalid)?;
+            let bytes = if list.trim().is_empty() {
+                vec![]
+            } else {
+                list.split(',')
+                    .map(|n| n.trim().parse().map_err(|_| invalid()))
+                    .collect::<Result<_, _>>()?
+            };
+            Ok((Constant::Bytes(bytes), rest))
+        }
+        "list" => {
+            let mut rest = rest_with_delim.trim_start().strip_prefix('[').ok_or_else(invalid)?;
+            let mut items = vec![];
+            loop {
+                rest = rest.trim_start();
+                if let Some(after) = rest.strip_prefix(']') {
+                    rest = after;
+                    break;
+                }
+                let (item, after) = parse_constant(rest)?;
+                items.push(item);
+                rest = after.trim_start();
+                rest = rest.strip_prefix(',').unwrap_or(rest);
+            }
+            Ok((Constant::List(items), rest))
+        }
+        "record" => {
+            let mut rest = rest_with_delim.trim_start().strip_prefix('{').ok_or_else(invalid)?;
+            let mut entries = vec![];
+            loop {
+                rest = rest.trim_start();
+                if let Some(after) = rest.strip_prefix('}') {
+                    rest = after;
+                    break;
+                }
+                let (key, after) = parse_quoted(rest)?;
+                let after = after.trim_start().strip_prefix(':').ok_or_else(invalid)?;
+                let (value, after) = parse_constant(after)?;
+                if let Some(existing) = entries.iter_mut().find(|(k, _): &&mut (String, Constant)| *k == key) {
+                    existing.1 = value;
+                } else {
+                    entries.push((key, value));
+                }
+                rest = after.trim_start();
+                rest = rest.strip_prefix(',').unwrap_or(rest);
+            }
+            Ok((Constant::Record(entries), rest))
+        }
+        _ => Err(invalid()),
+    }
+}
+/// Parse a `"..."` literal produced by Rust's `{:?}` formatting (as used by
+/// [`format_constant`]), undoing its backslash escapes so text containing
+/// `"` or `\` round-trips through [`disassemble`]/[`assemble`].
+fn parse_quoted(input: &str) -> Result<(String, &str), AsmError> {
+    let input = input.trim_start();
+    let invalid = || AsmError::InvalidOperand(input.to_string());
+    let rest = input.strip_prefix('"').ok_or_else(invalid)?;
+    let mut out = String::new();
+    let mut chars = rest.char_indices();
+    loop {
+        let (i, c) = chars.next().ok_or_else(invalid)?;
+        match c {
+            '"' => return Ok((out, &rest[i + 1..])),
+            '\\' => {
+                let (_, escaped) = chars.next().ok_or_else(invalid)?;
+                match escaped {
+                    '"' => out.push('"'),
+                    '\\' => out.push('\\'),
+                    'n' => out.push('\n'),
+                    'r' => out.push('\r'),
+                    't' => out.push('\t'),
+                    '0' => out.push('\0'),
+                    'u' => {
+                        if chars.next().map(|(_, c)| c) != Some('{') {
+                            return Err(invalid());
+                        }
+                        let mut code = String::new();
+                        loop {
+                            let (_, digit) = chars.next().ok_or_else(invalid)?;
+                            if digit == '}' {
+                                break;
+                            }
+                            code.push(digit);
+                        }
+                        let codepoint = u32::from_str_radix(&code, 16).map_err(|_| invalid())?;
+                        out.push(char::from_u32(codepoint).ok_or_else(invalid)?);
+                    }
+                    other => out.push(other),
+                }
+            }
+            other => out.push(other),
+        }
+    }
+}
+
+fn parse_locator(operand: &str, labels: &HashMap<&str, u32>) -> Result<Locator, AsmError> {
+    if let Some(label) = operand.strip_prefix('@') {
+        if let Some(hex) = label.strip_prefix("0x") {
+            return u32::from_str_radix(hex, 16)
+                .map(Locator::Address)
+                .map_err(|_| AsmError::InvalidOperand(operand.to_string()));
+        }
+        return labels
+            .get(label)
+            .copied()
+            .map(Locator::Address)
+            .ok_or_else(|| AsmError::UnknownLabel(label.to_string()));
+    }
+    parse_register(operand).map(Locator::FromRegister)
+}
+
+fn operands(line: &str) -> Vec<&str> {
+    line.split(',').map(str::trim).collect()
+}
+fn operand<'a>(ops: &[&'a str], index: usize, line: &str) -> Result<&'a str, AsmError> {
+    ops.get(index)
+        .copied()
+        .ok_or_else(|| AsmError::MissingOperand(line.to_string()))
+}
+
+/// Parse assembly text produced by [`disassemble`] (or hand-written in the
+/// same mnemonic form) back into a [`Program`].
+///
+/// Labels are resolved in two passes: the first walks the instruction lines
+/// to record each `label:` line's address, the second parses operands so
+/// forward jumps resolve correctly.
+pub fn assemble(source: &str) -> Result<Program, AsmError> {
+    let mut constants = vec![];
+    let mut instruction_lines = vec![];
+    for raw_line in source.lines() {
+        let line = raw_line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix(".const") {
+            let rest = rest.trim();
+            let (_, rest) = rest.split_once(' ').ok_or_else(|| AsmError::MissingOperand(line.to_string()))?;
+            let (constant, _) = parse_constant(rest)?;
+            constants.push(constant);
+            continue;
+        }
+        instruction_lines.push(line);
+    }
+
+    let mut labels = HashMap::new();
+    let mut address = 0u32;
+    for line in &instruction_lines {
+        if let Some(label) = line.strip_suffix(':') {
+            labels.insert(label, address);
+        } else {
+            address += 1;
+        }
+    }
+
+    let mut code = vec![];
+    for line in instruction_lines {
+        if line.ends_with(':') {
+            continue;
+        }
+        let (mnemonic, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let ops = operands(rest);
+        code.push(match mnemonic {
+            "none" => ByteCode::None,
+            "halt" => ByteCode::Halt,
+            "jmp" => ByteCode::Jump {
+                addr: parse_locator(operand(&ops, 0, line)?, &labels)?,
+            },
+            "jmpif" => ByteCode::JumpIf {
+                cond: parse_register(operand(&ops, 0, line)?)?,
+                addr: parse_locator(operand(&ops, 1, line)?, &labels)?,
+            },
+            "str" => ByteCode::String {
+                dst: parse_register(operand(&ops, 0, line)?)?,
+                addr: operand(&ops, 1, line)?
+                    .strip_prefix('#')
+                    .ok_or_else(|| AsmError::InvalidOperand(line.to_string()))
+                    .and_then(parse_u32)?,
+            },
+            "int" => ByteCode::Int {
+                dst: parse_register(operand(&ops, 0, line)?)?,
+                value: operand(&ops, 1, line)?
+                    .parse()
+                    .map_err(|_| AsmError::InvalidOperand(line.to_string()))?,
+            },
+            "float" => ByteCode::Float {
+                dst: parse_register(operand(&ops, 0, line)?)?,
+                value: operand(&ops, 1, line)?
+                    .parse()
+                    .map_err(|_| AsmError::InvalidOperand(line.to_string()))?,
+            },
+            "bool" => ByteCode::Bool {
+                dst: parse_register(operand(&ops, 0, line)?)?,
+                value: operand(&ops, 1, line)?
+                    .parse()
+                    .map_err(|_| AsmError::InvalidOperand(line.to_string()))?,
+            },
+            "move" => ByteCode::Move {
+                dst: parse_register(operand(&ops, 0, line)?)?,
+                src: parse_register(operand(&ops, 1, line)?)?,
+            },
+            "field" => ByteCode::Field {
+                dst: parse_register(operand(&ops, 0, line)?)?,
+                src: parse_register(operand(&ops, 1, line)?)?,
+                field: parse_u32(operand(&ops, 2, line)?)?,
+            },
+            "call" => ByteCode::Call {
+                dst: parse_register(operand(&ops, 0, line)?)?,
+                addr: parse_locator(operand(&ops, 1, line)?, &labels)?,
+                args: parse_u32(operand(&ops, 2, line)?)?,
+            },
+            "ecall" => ByteCode::Ecall {
+                dst: parse_register(operand(&ops, 0, line)?)?,
+                id: parse_u32(operand(&ops, 1, line)?)?,
+                args: parse_register(operand(&ops, 2, line)?)?,
+            },
+            "trap" => ByteCode::Trap {
+                code: parse_u32(operand(&ops, 0, line)?)?,
+            },
+            m if m.starts_with("load.") => ByteCode::Load {
+                dst: parse_register(operand(&ops, 0, line)?)?,
+                addr: parse_locator(operand(&ops, 1, line)?, &labels)?,
+                size: parse_width(m, line)?,
+            },
+            m if m.starts_with("store.") => ByteCode::Store {
+                addr: parse_locator(operand(&ops, 0, line)?, &labels)?,
+                src: parse_register(operand(&ops, 1, line)?)?,
+                size: parse_width(m, line)?,
+            },
+            "const" => ByteCode::Const {
+                dst: parse_register(operand(&ops, 0, line)?)?,
+                index: operand(&ops, 1, line)?
+                    .strip_prefix('#')
+                    .ok_or_else(|| AsmError::InvalidOperand(line.to_string()))
+                    .and_then(parse_u32)?,
+            },
+            "tick" => ByteCode::Tick {
+                dst: parse_register(operand(&ops, 0, line)?)?,
+            },
+            "add" | "sub" | "div" | "mul" | "mod" | "eq" | "ne" | "lt" | "le" | "gt" | "ge"
+            | "and" | "or" | "band" | "bor" | "bxor" | "shl" | "shr" => ByteCode::Binary {
+                op: match mnemonic {
+                    "add" => BinaryOperation::Add,
+                    "sub" => BinaryOperation::Sub,
+                    "div" => BinaryOperation::Div,
+                    "mul" => BinaryOperation::Mul,
+                    "mod" => BinaryOperation::Mod,
+                    "eq" => BinaryOperation::Eq,
+                    "ne" => BinaryOperation::Ne,
+                    "lt" => BinaryOperation::Lt,
+                    "le" => BinaryOperation::Le,
+                    "gt" => BinaryOperation::Gt,
+                    "ge" => BinaryOperation::Ge,
+                    "and" => BinaryOperation::And,
+                    "or" => BinaryOperation::Or,
+                    "band" => BinaryOperation::BitAnd,
+                    "bor" => BinaryOperation::BitOr,
+                    "bxor" => BinaryOperation::BitXor,
+                    "shl" => BinaryOperation::Shl,
+                    "shr" => BinaryOperation::Shr,
+                    _ => unreachable!(),
+                },
+                dst: parse_register(operand(&ops, 0, line)?)?,
+                left: parse_register(operand(&ops, 1, line)?)?,
+                right: parse_register(operand(&ops, 2, line)?)?,
+            },
+            "neg" | "not" | "bnot" => ByteCode::Unary {
+                op: match mnemonic {
+                    "neg" => UnaryOperation::Neg,
+                    "not" => UnaryOperation::Not,
+                    "bnot" => UnaryOperation::BitNot,
+                    _ => unreachable!(),
+                },
+                dst: parse_register(operand(&ops, 0, line)?)?,
+                right: parse_register(operand(&ops, 1, line)?)?,
+            },
+            other => return Err(AsmError::UnknownMnemonic(other.to_string())),
+        });
+    }
+
+    Ok(Program { constants, code })
+}
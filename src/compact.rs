@@ -0,0 +1,366 @@
+//! A variable-length alternative to the fixed 13-byte-per-instruction layout
+//! in [`crate`]. Each operand is stored in the smallest field that fits it,
+//! which shrinks programs that are mostly small register indices (the
+//! common case for jumps, moves and binary ops).
+//!
+//! An operand occupies a little-endian field whose first byte carries, in
+//! its low 3 bits, a 2-bit width tag (`1` = 16-bit field, `2` = 32-bit
+//! field, `3` = 48-bit field) and a register/constant flag. The remaining
+//! bits of the field hold the operand's value. Because a [`Locator`] is
+//! really just a register-or-constant operand, `Jump`/`JumpIf`/`Call` don't
+//! need the two opcodes the fixed format uses for `Address` vs
+//! `FromRegister` — the operand's own flag carries that distinction.
+//!
+//! `Int`/`Float` immediates carry a full `u64` rather than a register or
+//! address, so they get their own width tag (a leading byte, since the
+//! value itself can need all 64 bits and has no spare bits to pack a tag
+//! into) instead of wasting the fixed format's full 8-byte word on small
+//! or zero immediates.
+
+use crate::{
+    constant::{Constant, ConstantError},
+    memory::Width,
+    BinaryOperation, ByteCode, ByteCodeError, Locator, Program, Register, UnaryOperation,
+};
+use std::{error::Error, fmt::Display};
+
+const TAG_16: u8 = 1;
+const TAG_32: u8 = 2;
+const TAG_48: u8 = 3;
+const REGISTER_FLAG: u64 = 0b100;
+
+const WIDE_8: u8 = 0;
+const WIDE_16: u8 = 1;
+const WIDE_32: u8 = 2;
+const WIDE_64: u8 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompactError {
+    InsufficientBytes,
+    InvalidTag(u8),
+    ByteCodeError(ByteCodeError),
+    ConstantError(ConstantError),
+}
+impl Display for CompactError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompactError::InsufficientBytes => write!(f, "insufficient bytes"),
+            CompactError::InvalidTag(tag) => write!(f, "invalid operand width tag {tag}"),
+            CompactError::ByteCodeError(err) => err.fmt(f),
+            CompactError::ConstantError(err) => err.fmt(f),
+        }
+    }
+}
+impl Error for CompactError {}
+
+fn write_operand(bytes: &mut Vec<u8>, value: u32, is_register: bool) {
+    let payload = value as u64;
+    let flag = if is_register { REGISTER_FLAG } else { 0 };
+    if payload < (1 << 13) {
+        let word = ((payload << 3) | flag | TAG_16 as u64) as u16;
+        bytes.extend(word.to_le_bytes());
+    } else if payload < (1 << 29) {
+        let word = ((payload << 3) | flag | TAG_32 as u64) as u32;
+        bytes.extend(word.to_le_bytes());
+    } else {
+        let word = (payload << 3) | flag | TAG_48 as u64;
+        bytes.extend(&word.to_le_bytes()[..6]);
+    }
+}
+fn read_operand(bytes: &mut impl Iterator<Item = u8>) -> Result<(u32, bool), CompactError> {
+    let first = bytes.next().ok_or(CompactError::InsufficientBytes)?;
+    let width = match first & 0b11 {
+        1 => 2,
+        2 => 4,
+        3 => 6,
+        tag => return Err(CompactError::InvalidTag(tag)),
+    };
+    let mut word_bytes = [0u8; 8];
+    word_bytes[0] = first;
+    for byte in word_bytes.iter_mut().take(width).skip(1) {
+        *byte = bytes.next().ok_or(CompactError::InsufficientBytes)?;
+    }
+    let word = u64::from_le_bytes(word_bytes);
+    Ok(((word >> 3) as u32, word & REGISTER_FLAG != 0))
+}
+/// Write a 64-bit immediate (an `Int`/`Float` payload) in the smallest of
+/// 1/2/4/8 bytes that fits, tagged with a leading width byte.
+fn write_wide(bytes: &mut Vec<u8>, value: u64) {
+    if value <= u8::MAX as u64 {
+        bytes.push(WIDE_8);
+        bytes.push(value as u8);
+    } else if value <= u16::MAX as u64 {
+        bytes.push(WIDE_16);
+        bytes.extend((value as u16).to_le_bytes());
+    } else if value <= u32::MAX as u64 {
+        bytes.push(WIDE_32);
+        bytes.extend((value as u32).to_le_bytes());
+    } else {
+        bytes.push(WIDE_64);
+        bytes.extend(value.to_le_bytes());
+    }
+}
+fn read_wide(bytes: &mut impl Iterator<Item = u8>) -> Result<u64, CompactError> {
+    let tag = bytes.next().ok_or(CompactError::InsufficientBytes)?;
+    Ok(match tag {
+        WIDE_8 => bytes.next().ok_or(CompactError::InsufficientBytes)? as u64,
+        WIDE_16 => {
+            let (Some(b0), Some(b1)) = (bytes.next(), bytes.next()) else {
+                return Err(CompactError::InsufficientBytes);
+            };
+            u16::from_le_bytes([b0, b1]) as u64
+        }
+        WIDE_32 => {
+            let mut word = [0u8; 4];
+            for byte in &mut word {
+                *byte = bytes.next().ok_or(CompactError::InsufficientBytes)?;
+            }
+            u32::from_le_bytes(word) as u64
+        }
+        WIDE_64 => {
+            let mut word = [0u8; 8];
+            for byte in &mut word {
+                *byte = bytes.next().ok_or(CompactError::InsufficientBytes)?;
+            }
+            u64::from_le_bytes(word)
+        }
+        tag => return Err(CompactError::InvalidTag(tag)),
+    })
+}
+fn write_locator(bytes: &mut Vec<u8>, locator: Locator) {
+    match locator {
+        Locator::Address(addr) => write_operand(bytes, addr, false),
+        Locator::FromRegister(reg) => write_operand(bytes, reg, true),
+    }
+}
+fn read_locator(bytes: &mut impl Iterator<Item = u8>) -> Result<Locator, CompactError> {
+    let (value, is_register) = read_operand(bytes)?;
+    Ok(if is_register {
+        Locator::FromRegister(value)
+    } else {
+        Locator::Address(value)
+    })
+}
+
+impl Program {
+    /// Encode using the variable-length operand layout described in this
+    /// module, instead of the fixed 13-byte-per-instruction form.
+    pub fn encode_compact(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+
+        bytes.extend((self.constants.len() as u32).to_be_bytes());
+        for constant in &self.constants {
+            constant.encode(&mut bytes);
+        }
+
+        for &bytecode in &self.code {
+            match bytecode {
+                ByteCode::None => bytes.push(0x00),
+                ByteCode::Halt => bytes.push(0x01),
+                ByteCode::Jump { addr } => {
+                    bytes.push(0x02);
+                    write_locator(&mut bytes, addr);
+                }
+                ByteCode::JumpIf { cond, addr } => {
+                    bytes.push(0x04);
+                    write_operand(&mut bytes, cond, true);
+                    write_locator(&mut bytes, addr);
+                }
+                ByteCode::String { dst, addr } => {
+                    bytes.push(0x10);
+                    write_operand(&mut bytes, dst, true);
+                    write_operand(&mut bytes, addr, false);
+                }
+                ByteCode::Int { dst, value } => {
+                    bytes.push(0x11);
+                    write_operand(&mut bytes, dst, true);
+                    write_wide(&mut bytes, value);
+                }
+                ByteCode::Float { dst, value } => {
+                    bytes.push(0x12);
+                    write_operand(&mut bytes, dst, true);
+                    write_wide(&mut bytes, value.to_bits());
+                }
+                ByteCode::Bool { dst, value } => {
+                    bytes.push(0x13);
+                    write_operand(&mut bytes, dst, true);
+                    write_operand(&mut bytes, value as u32, false);
+                }
+                ByteCode::Move { dst, src } => {
+                    bytes.push(0x20);
+                    write_operand(&mut bytes, dst, true);
+                    write_operand(&mut bytes, src, true);
+                }
+                ByteCode::Field { dst, src, field } => {
+                    bytes.push(0x21);
+                    write_operand(&mut bytes, dst, true);
+                    write_operand(&mut bytes, src, true);
+                    write_operand(&mut bytes, field, false);
+                }
+                ByteCode::Call { addr, args, dst } => {
+                    bytes.push(0x22);
+                    write_locator(&mut bytes, addr);
+                    write_operand(&mut bytes, args, false);
+                    write_operand(&mut bytes, dst, true);
+                }
+                ByteCode::Ecall { id, args, dst } => {
+                    bytes.push(0x24);
+                    write_operand(&mut bytes, id, false);
+                    write_operand(&mut bytes, args, true);
+                    write_operand(&mut bytes, dst, true);
+                }
+                ByteCode::Trap { code } => {
+                    bytes.push(0x25);
+                    write_operand(&mut bytes, code, false);
+                }
+                ByteCode::Load { dst, addr, size } => {
+                    bytes.push(0x26);
+                    write_operand(&mut bytes, dst, true);
+                    write_locator(&mut bytes, addr);
+                    write_operand(&mut bytes, size.into(), false);
+                }
+                ByteCode::Store { src, addr, size } => {
+                    bytes.push(0x27);
+                    write_operand(&mut bytes, src, true);
+                    write_locator(&mut bytes, addr);
+                    write_operand(&mut bytes, size.into(), false);
+                }
+                ByteCode::Const { dst, index } => {
+                    bytes.push(0x28);
+                    write_operand(&mut bytes, dst, true);
+                    write_operand(&mut bytes, index, false);
+                }
+                ByteCode::Tick { dst } => {
+                    bytes.push(0x29);
+                    write_operand(&mut bytes, dst, true);
+                }
+                ByteCode::Binary { op, dst, left, right } => {
+                    bytes.push(0x30 + op as u8);
+                    write_operand(&mut bytes, dst, true);
+                    write_operand(&mut bytes, left, true);
+                    write_operand(&mut bytes, right, true);
+                }
+                ByteCode::Unary { op, dst, right } => {
+                    bytes.push(0x50 + op as u8);
+                    write_operand(&mut bytes, dst, true);
+                    write_operand(&mut bytes, right, true);
+                }
+            }
+        }
+
+        bytes
+    }
+
+    /// Decode a program produced by [`Program::encode_compact`].
+    pub fn decode_compact(value: &[u8]) -> Result<Self, CompactError> {
+        let mut bytes = value.iter().copied();
+
+        let size = {
+            let (Some(n1), Some(n2), Some(n3), Some(n4)) =
+                (bytes.next(), bytes.next(), bytes.next(), bytes.next())
+            else {
+                return Err(CompactError::InsufficientBytes);
+            };
+            u32::from_be_bytes([n1, n2, n3, n4])
+        };
+        let mut constants = Vec::with_capacity((size as usize).min(bytes.len()));
+        for _ in 0..size {
+            constants.push(Constant::decode(&mut bytes).map_err(CompactError::ConstantError)?);
+        }
+
+        let mut code = vec![];
+        while let Some(opcode) = bytes.next() {
+            code.push(match opcode {
+                0x00 => ByteCode::None,
+                0x01 => ByteCode::Halt,
+                0x02 => ByteCode::Jump {
+                    addr: read_locator(&mut bytes)?,
+                },
+                0x04 => ByteCode::JumpIf {
+                    cond: read_operand(&mut bytes)?.0,
+                    addr: read_locator(&mut bytes)?,
+                },
+                0x10 => ByteCode::String {
+                    dst: read_operand(&mut bytes)?.0,
+                    addr: read_operand(&mut bytes)?.0,
+                },
+                0x11 => {
+                    let dst = read_operand(&mut bytes)?.0;
+                    ByteCode::Int {
+                        dst,
+                        value: read_wide(&mut bytes)?,
+                    }
+                }
+                0x12 => {
+                    let dst = read_operand(&mut bytes)?.0;
+                    ByteCode::Float {
+                        dst,
+                        value: f64::from_bits(read_wide(&mut bytes)?),
+                    }
+                }
+                0x13 => ByteCode::Bool {
+                    dst: read_operand(&mut bytes)?.0,
+                    value: read_operand(&mut bytes)?.0 != 0,
+                },
+                0x20 => ByteCode::Move {
+                    dst: read_operand(&mut bytes)?.0,
+                    src: read_operand(&mut bytes)?.0,
+                },
+                0x21 => ByteCode::Field {
+                    dst: read_operand(&mut bytes)?.0,
+                    src: read_operand(&mut bytes)?.0,
+                    field: read_operand(&mut bytes)?.0,
+                },
+                0x22 => {
+                    let addr = read_locator(&mut bytes)?;
+                    let args = read_operand(&mut bytes)?.0;
+                    let dst: Register = read_operand(&mut bytes)?.0;
+                    ByteCode::Call { addr, args, dst }
+                }
+                0x24 => ByteCode::Ecall {
+                    id: read_operand(&mut bytes)?.0,
+                    args: read_operand(&mut bytes)?.0,
+                    dst: read_operand(&mut bytes)?.0,
+                },
+                0x25 => ByteCode::Trap {
+                    code: read_operand(&mut bytes)?.0,
+                },
+                0x26 => ByteCode::Load {
+                    dst: read_operand(&mut bytes)?.0,
+                    addr: read_locator(&mut bytes)?,
+                    size: Width::try_from(read_operand(&mut bytes)?.0)
+                        .map_err(|err| CompactError::ByteCodeError(err.into()))?,
+                },
+                0x27 => ByteCode::Store {
+                    src: read_operand(&mut bytes)?.0,
+                    addr: read_locator(&mut bytes)?,
+                    size: Width::try_from(read_operand(&mut bytes)?.0)
+                        .map_err(|err| CompactError::ByteCodeError(err.into()))?,
+                },
+                0x28 => ByteCode::Const {
+                    dst: read_operand(&mut bytes)?.0,
+                    index: read_operand(&mut bytes)?.0,
+                },
+                0x29 => ByteCode::Tick {
+                    dst: read_operand(&mut bytes)?.0,
+                },
+                0x30..=0x4f => ByteCode::Binary {
+                    op: BinaryOperation::try_from(opcode - 0x30)
+                        .map_err(|_| CompactError::ByteCodeError(ByteCodeError::InvalidBinaryOperation(opcode - 0x30)))?,
+                    dst: read_operand(&mut bytes)?.0,
+                    left: read_operand(&mut bytes)?.0,
+                    right: read_operand(&mut bytes)?.0,
+                },
+                0x50..=0x5f => ByteCode::Unary {
+                    op: UnaryOperation::try_from(opcode - 0x50)
+                        .map_err(|_| CompactError::ByteCodeError(ByteCodeError::InvalidUnaryOperation(opcode - 0x50)))?,
+                    dst: read_operand(&mut bytes)?.0,
+                    right: read_operand(&mut bytes)?.0,
+                },
+                _ => return Err(CompactError::ByteCodeError(ByteCodeError::InvalidOperation)),
+            });
+        }
+
+        Ok(Self { constants, code })
+    }
+}
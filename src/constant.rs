@@ -0,0 +1,235 @@
+//! A self-describing constant pool. Each [`Constant`] is tagged with a
+//! one-byte type id followed by a length-prefixed payload (the length
+//! counts payload bytes, never UTF-8 codepoints), so [`Program`]s can embed
+//! structured literals — not just flat strings — and round-trip full
+//! Unicode instead of truncating to one byte per `char`.
+//!
+//! [`Program`]: crate::Program
+
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    Unit,
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+    List(Vec<Constant>),
+    /// Key/value pairs, in insertion order. A later entry with a key that
+    /// already exists overrides the earlier entry's value in place.
+    Record(Vec<(String, Constant)>),
+}
+
+/// Nesting limit for [`Constant::List`]/[`Constant::Record`] decoding, chosen
+/// to fit comfortably within a thread's default stack so adversarial input
+/// can't drive the recursive decoder into a stack overflow.
+const MAX_NESTING_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstantError {
+    InsufficientBytes,
+    InvalidTag(u8),
+    InvalidUtf8,
+    NestingTooDeep,
+}
+impl Display for ConstantError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConstantError::InsufficientBytes => write!(f, "insufficient bytes"),
+            ConstantError::InvalidTag(tag) => write!(f, "invalid constant tag {tag}"),
+            ConstantError::InvalidUtf8 => write!(f, "invalid utf-8 in constant"),
+            ConstantError::NestingTooDeep => write!(f, "constant nesting too deep"),
+        }
+    }
+}
+impl Error for ConstantError {}
+
+fn write_payload(out: &mut Vec<u8>, payload: &[u8]) {
+    out.extend((payload.len() as u32).to_be_bytes());
+    out.extend(payload);
+}
+
+impl Constant {
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Constant::Unit => {
+                out.push(0);
+                write_payload(out, &[]);
+            }
+            Constant::Bool(value) => {
+                out.push(1);
+                write_payload(out, &[*value as u8]);
+            }
+            Constant::I8(value) => {
+                out.push(2);
+                write_payload(out, &value.to_be_bytes());
+            }
+            Constant::I16(value) => {
+                out.push(3);
+                write_payload(out, &value.to_be_bytes());
+            }
+            Constant::I32(value) => {
+                out.push(4);
+                write_payload(out, &value.to_be_bytes());
+            }
+            Constant::I64(value) => {
+                out.push(5);
+                write_payload(out, &value.to_be_bytes());
+            }
+            Constant::U8(value) => {
+                out.push(6);
+                write_payload(out, &value.to_be_bytes());
+            }
+            Constant::U16(value) => {
+                out.push(7);
+                write_payload(out, &value.to_be_bytes());
+            }
+            Constant::U32(value) => {
+                out.push(8);
+                write_payload(out, &value.to_be_bytes());
+            }
+            Constant::U64(value) => {
+                out.push(9);
+                write_payload(out, &value.to_be_bytes());
+            }
+            Constant::Float(value) => {
+                out.push(10);
+                write_payload(out, &value.to_bits().to_be_bytes());
+            }
+            Constant::Text(value) => {
+                out.push(11);
+                write_payload(out, value.as_bytes());
+            }
+            Constant::Bytes(value) => {
+                out.push(12);
+                write_payload(out, value);
+            }
+            Constant::List(items) => {
+                let mut payload = (items.len() as u32).to_be_bytes().to_vec();
+                for item in items {
+                    item.encode(&mut payload);
+                }
+                out.push(13);
+                write_payload(out, &payload);
+            }
+            Constant::Record(entries) => {
+                let mut payload = (entries.len() as u32).to_be_bytes().to_vec();
+                for (key, value) in entries {
+                    payload.extend((key.len() as u32).to_be_bytes());
+                    payload.extend(key.as_bytes());
+                    value.encode(&mut payload);
+                }
+                out.push(14);
+                write_payload(out, &payload);
+            }
+        }
+    }
+
+    pub fn decode(bytes: &mut impl Iterator<Item = u8>) -> Result<Self, ConstantError> {
+        Self::decode_nested(bytes, 0)
+    }
+
+    fn decode_nested(bytes: &mut impl Iterator<Item = u8>, depth: usize) -> Result<Self, ConstantError> {
+        let tag = bytes.next().ok_or(ConstantError::InsufficientBytes)?;
+        let len = {
+            let (Some(n1), Some(n2), Some(n3), Some(n4)) =
+                (bytes.next(), bytes.next(), bytes.next(), bytes.next())
+            else {
+                return Err(ConstantError::InsufficientBytes);
+            };
+            u32::from_be_bytes([n1, n2, n3, n4]) as usize
+        };
+        let payload: Vec<u8> = bytes.by_ref().take(len).collect();
+        if payload.len() != len {
+            return Err(ConstantError::InsufficientBytes);
+        }
+        Self::decode_payload(tag, &payload, depth)
+    }
+
+    fn decode_payload(tag: u8, payload: &[u8], depth: usize) -> Result<Self, ConstantError> {
+        fn fixed<const N: usize>(payload: &[u8]) -> Result<[u8; N], ConstantError> {
+            payload.try_into().map_err(|_| ConstantError::InsufficientBytes)
+        }
+        Ok(match tag {
+            0 => Constant::Unit,
+            1 => Constant::Bool(*payload.first().ok_or(ConstantError::InsufficientBytes)? != 0),
+            2 => Constant::I8(i8::from_be_bytes(fixed(payload)?)),
+            3 => Constant::I16(i16::from_be_bytes(fixed(payload)?)),
+            4 => Constant::I32(i32::from_be_bytes(fixed(payload)?)),
+            5 => Constant::I64(i64::from_be_bytes(fixed(payload)?)),
+            6 => Constant::U8(u8::from_be_bytes(fixed(payload)?)),
+            7 => Constant::U16(u16::from_be_bytes(fixed(payload)?)),
+            8 => Constant::U32(u32::from_be_bytes(fixed(payload)?)),
+            9 => Constant::U64(u64::from_be_bytes(fixed(payload)?)),
+            10 => Constant::Float(f64::from_bits(u64::from_be_bytes(fixed(payload)?))),
+            11 => Constant::Text(String::from_utf8(payload.to_vec()).map_err(|_| ConstantError::InvalidUtf8)?),
+            12 => Constant::Bytes(payload.to_vec()),
+            13 => {
+                if depth >= MAX_NESTING_DEPTH {
+                    return Err(ConstantError::NestingTooDeep);
+                }
+                let mut bytes = payload.iter().copied();
+                let count = {
+                    let (Some(n1), Some(n2), Some(n3), Some(n4)) =
+                        (bytes.next(), bytes.next(), bytes.next(), bytes.next())
+                    else {
+                        return Err(ConstantError::InsufficientBytes);
+                    };
+                    u32::from_be_bytes([n1, n2, n3, n4])
+                };
+                let mut items = Vec::with_capacity((count as usize).min(bytes.len()));
+                for _ in 0..count {
+                    items.push(Constant::decode_nested(&mut bytes, depth + 1)?);
+                }
+                Constant::List(items)
+            }
+            14 => {
+                if depth >= MAX_NESTING_DEPTH {
+                    return Err(ConstantError::NestingTooDeep);
+                }
+                let mut bytes = payload.iter().copied();
+                let count = {
+                    let (Some(n1), Some(n2), Some(n3), Some(n4)) =
+                        (bytes.next(), bytes.next(), bytes.next(), bytes.next())
+                    else {
+                        return Err(ConstantError::InsufficientBytes);
+                    };
+                    u32::from_be_bytes([n1, n2, n3, n4])
+                };
+                let mut entries: Vec<(String, Constant)> = Vec::with_capacity((count as usize).min(bytes.len()));
+                for _ in 0..count {
+                    let key_len = {
+                        let (Some(n1), Some(n2), Some(n3), Some(n4)) =
+                            (bytes.next(), bytes.next(), bytes.next(), bytes.next())
+                        else {
+                            return Err(ConstantError::InsufficientBytes);
+                        };
+                        u32::from_be_bytes([n1, n2, n3, n4]) as usize
+                    };
+                    let key_bytes: Vec<u8> = bytes.by_ref().take(key_len).collect();
+                    if key_bytes.len() != key_len {
+                        return Err(ConstantError::InsufficientBytes);
+                    }
+                    let key = String::from_utf8(key_bytes).map_err(|_| ConstantError::InvalidUtf8)?;
+                    let value = Constant::decode_nested(&mut bytes, depth + 1)?;
+                    if let Some(existing) = entries.iter_mut().find(|(k, _)| *k == key) {
+                        existing.1 = value;
+                    } else {
+                        entries.push((key, value));
+                    }
+                }
+                Constant::Record(entries)
+            }
+            _ => return Err(ConstantError::InvalidTag(tag)),
+        })
+    }
+}
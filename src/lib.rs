@@ -1,6 +1,13 @@
 #[cfg(test)]
 mod tests;
+pub mod asm;
+pub mod compact;
+pub mod constant;
+pub mod memory;
+pub mod vm;
 
+use constant::Constant;
+use memory::{InvalidWidth, Width};
 use std::{error::Error, fmt::Display};
 
 pub type Register = u32;
@@ -55,6 +62,33 @@ pub enum ByteCode {
         args: u32,
         dst: Register
     },
+    Ecall {
+        id: u32,
+        args: Register,
+        dst: Register,
+    },
+    Trap {
+        code: u32,
+    },
+    Load {
+        dst: Register,
+        addr: Locator,
+        size: Width,
+    },
+    Store {
+        src: Register,
+        addr: Locator,
+        size: Width,
+    },
+    Const {
+        dst: Register,
+        index: u32,
+    },
+    /// Read the interpreter's wrapping elapsed-step counter into `dst`,
+    /// giving a program a notion of time without real-world clock access.
+    Tick {
+        dst: Register,
+    },
 
     Binary {
         op: BinaryOperation,
@@ -76,6 +110,23 @@ pub enum BinaryOperation {
     Sub,
     Div,
     Mul,
+    Mod,
+    /// Yields `1` if equal, `0` otherwise — usable directly by `JumpIf`.
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// Logical AND/OR: treats operands as booleans (zero/non-zero), unlike
+    /// `BitAnd`/`BitOr` which operate bit-by-bit.
+    And,
+    Or,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
 }
 #[derive(Debug, Clone, PartialEq)]
 pub struct BinaryOperationError;
@@ -87,6 +138,20 @@ impl TryFrom<u8> for BinaryOperation {
             1 => Ok(Self::Sub),
             2 => Ok(Self::Div),
             3 => Ok(Self::Mul),
+            4 => Ok(Self::Mod),
+            5 => Ok(Self::Eq),
+            6 => Ok(Self::Ne),
+            7 => Ok(Self::Lt),
+            8 => Ok(Self::Le),
+            9 => Ok(Self::Gt),
+            10 => Ok(Self::Ge),
+            11 => Ok(Self::And),
+            12 => Ok(Self::Or),
+            13 => Ok(Self::BitAnd),
+            14 => Ok(Self::BitOr),
+            15 => Ok(Self::BitXor),
+            16 => Ok(Self::Shl),
+            17 => Ok(Self::Shr),
             _ => Err(BinaryOperationError),
         }
     }
@@ -98,6 +163,20 @@ impl From<BinaryOperation> for u8 {
             BinaryOperation::Sub => 1,
             BinaryOperation::Div => 2,
             BinaryOperation::Mul => 3,
+            BinaryOperation::Mod => 4,
+            BinaryOperation::Eq => 5,
+            BinaryOperation::Ne => 6,
+            BinaryOperation::Lt => 7,
+            BinaryOperation::Le => 8,
+            BinaryOperation::Gt => 9,
+            BinaryOperation::Ge => 10,
+            BinaryOperation::And => 11,
+            BinaryOperation::Or => 12,
+            BinaryOperation::BitAnd => 13,
+            BinaryOperation::BitOr => 14,
+            BinaryOperation::BitXor => 15,
+            BinaryOperation::Shl => 16,
+            BinaryOperation::Shr => 17,
         }
     }
 }
@@ -105,6 +184,9 @@ impl From<BinaryOperation> for u8 {
 #[repr(u8)]
 pub enum UnaryOperation {
     Neg,
+    /// Logical NOT: yields `1` if `right` is zero, `0` otherwise.
+    Not,
+    BitNot,
 }
 #[derive(Debug, Clone, PartialEq)]
 pub struct UnaryOperationError;
@@ -113,6 +195,8 @@ impl TryFrom<u8> for UnaryOperation {
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
             0 => Ok(Self::Neg),
+            1 => Ok(Self::Not),
+            2 => Ok(Self::BitNot),
             _ => Err(UnaryOperationError),
         }
     }
@@ -121,6 +205,8 @@ impl From<UnaryOperation> for u8 {
     fn from(val: UnaryOperation) -> Self {
         match val {
             UnaryOperation::Neg => 0,
+            UnaryOperation::Not => 1,
+            UnaryOperation::BitNot => 2,
         }
     }
 }
@@ -160,8 +246,20 @@ impl From<ByteCode> for Bytes {
                 Locator::Address(addr) => (0x22, addr, args, dst),
                 Locator::FromRegister(addr) => (0x23, addr, args, dst),
             }
+            ByteCode::Ecall { id, args, dst } => (0x24, id, args, dst),
+            ByteCode::Trap { code } => (0x25, code, 0, 0),
+            ByteCode::Load { dst, addr, size } => match addr {
+                Locator::Address(addr) => (0x26, dst, addr, size.into()),
+                Locator::FromRegister(addr) => (0x27, dst, addr, size.into()),
+            }
+            ByteCode::Store { src, addr, size } => match addr {
+                Locator::Address(addr) => (0x28, src, addr, size.into()),
+                Locator::FromRegister(addr) => (0x29, src, addr, size.into()),
+            }
+            ByteCode::Const { dst, index } => (0x2a, dst, index, 0),
+            ByteCode::Tick { dst } => (0x2b, dst, 0, 0),
             ByteCode::Binary { op, dst, left, right } => (0x30 + op as u8, dst, left, right),
-            ByteCode::Unary { op, dst, right } => (0x40 + op as u8, dst, right, 0),
+            ByteCode::Unary { op, dst, right } => (0x50 + op as u8, dst, right, 0),
         }
     }
 }
@@ -171,20 +269,27 @@ pub enum ByteCodeError {
     InvalidOperation,
     InvalidBinaryOperation(u8),
     InvalidUnaryOperation(u8),
+    InvalidWidth(u32),
 }
 impl Display for ByteCodeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ByteCodeError::InvalidOperation => write!(f, "invalid operation"),
             ByteCodeError::InvalidBinaryOperation(op) => {
-                write!(f, "invalid binary operation 0x20 + 0x{op:2x?}")
+                write!(f, "invalid binary operation 0x30 + 0x{op:2x?}")
             }
             ByteCodeError::InvalidUnaryOperation(op) => {
-                write!(f, "invalid unary operation 0x30 + 0x{op:2x?}")
+                write!(f, "invalid unary operation 0x50 + 0x{op:2x?}")
             }
+            ByteCodeError::InvalidWidth(width) => write!(f, "invalid load/store width {width}"),
         }
     }
 }
+impl From<InvalidWidth> for ByteCodeError {
+    fn from(value: InvalidWidth) -> Self {
+        ByteCodeError::InvalidWidth(value.0)
+    }
+}
 impl Error for ByteCodeError {}
 impl TryFrom<Bytes> for ByteCode {
     type Error = ByteCodeError;
@@ -243,17 +348,48 @@ impl TryFrom<Bytes> for ByteCode {
                 args: value.2,
                 dst: value.3
             }),
+            0x24 => Ok(Self::Ecall {
+                id: value.1,
+                args: value.2,
+                dst: value.3,
+            }),
+            0x25 => Ok(Self::Trap { code: value.1 }),
+            0x26 => Ok(Self::Load {
+                dst: value.1,
+                addr: Locator::Address(value.2),
+                size: Width::try_from(value.3)?,
+            }),
+            0x27 => Ok(Self::Load {
+                dst: value.1,
+                addr: Locator::FromRegister(value.2),
+                size: Width::try_from(value.3)?,
+            }),
+            0x28 => Ok(Self::Store {
+                src: value.1,
+                addr: Locator::Address(value.2),
+                size: Width::try_from(value.3)?,
+            }),
+            0x29 => Ok(Self::Store {
+                src: value.1,
+                addr: Locator::FromRegister(value.2),
+                size: Width::try_from(value.3)?,
+            }),
+            0x2a => Ok(Self::Const {
+                dst: value.1,
+                index: value.2,
+            }),
+            0x2b => Ok(Self::Tick { dst: value.1 }),
 
-            0x30..=0x3f => Ok(Self::Binary {
-                op: BinaryOperation::try_from(value.0 - 0x20)
-                    .map_err(|_| ByteCodeError::InvalidBinaryOperation(value.0 - 0x20))?,
+            0x30..=0x4f => Ok(Self::Binary {
+                op: BinaryOperation::try_from(value.0 - 0x30)
+                    .map_err(|_| ByteCodeError::InvalidBinaryOperation(value.0 - 0x30))?,
                 dst: value.1,
                 left: value.2,
                 right: value.3,
             }),
-            0x40..=0x4f => Ok(Self::Unary {
-                op: UnaryOperation::try_from(value.0 - 0x20)
-                    .map_err(|_| ByteCodeError::InvalidUnaryOperation(value.0 - 0x30))?,
+            0x50..=0x5f => Ok(Self::Unary {
+                op: UnaryOperation::try_from(value.0 - 0x50)
+                    .map_err(|_| ByteCodeError::InvalidUnaryOperation(value.0 - 0x50))?,
                 dst: value.1,
                 right: value.2,
             }),
@@ -264,19 +400,21 @@ impl TryFrom<Bytes> for ByteCode {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Program {
-    strings: Vec<String>,
-    code: Vec<ByteCode>,
+    pub(crate) constants: Vec<Constant>,
+    pub(crate) code: Vec<ByteCode>,
 }
 #[derive(Debug, Clone, PartialEq)]
 pub enum ProgramParseError {
     InsufficiantBytes,
-    ByteCodeError(ByteCodeError)
+    ByteCodeError(ByteCodeError),
+    ConstantError(constant::ConstantError),
 }
 impl Display for ProgramParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ProgramParseError::InsufficiantBytes => write!(f, "insufficiant bytes"),
             ProgramParseError::ByteCodeError(err) => err.fmt(f),
+            ProgramParseError::ConstantError(err) => err.fmt(f),
         }
     }
 }
@@ -284,48 +422,35 @@ impl Error for ProgramParseError {}
 impl TryFrom<&[u8]> for Program {
     type Error = ProgramParseError;
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        let mut bytes = value.iter();
+        let mut bytes = value.iter().copied();
 
         let size = {
-            let (Some(n1), Some(n2), Some(n3), Some(n4)) = (bytes.next().copied(), bytes.next().copied(), bytes.next().copied(), bytes.next().copied()) else {
+            let (Some(n1), Some(n2), Some(n3), Some(n4)) = (bytes.next(), bytes.next(), bytes.next(), bytes.next()) else {
                 return Err(ProgramParseError::InsufficiantBytes);
             };
             u32::from_be_bytes([n1, n2, n3, n4])
         };
-        let mut strings = Vec::with_capacity(size as usize);
+        let mut constants = Vec::with_capacity((size as usize).min(bytes.len()));
         for _ in 0..size {
-            let string_size = {
-                let (Some(n1), Some(n2), Some(n3), Some(n4)) = (bytes.next().copied(), bytes.next().copied(), bytes.next().copied(), bytes.next().copied()) else {
-                    return Err(ProgramParseError::InsufficiantBytes);
-                };
-                u32::from_be_bytes([n1, n2, n3, n4])
-            };
-            let mut string = String::new();
-            for _ in 0..string_size {
-                let Some(c) = bytes.next().copied() else {
-                    return Err(ProgramParseError::InsufficiantBytes);
-                };
-                string.push(c as char);
-            }
-            strings.push(string);
+            constants.push(Constant::decode(&mut bytes).map_err(ProgramParseError::ConstantError)?);
         }
 
         let mut code = vec![];
-        while let Some(instr) = bytes.next().copied() {
+        while let Some(instr) = bytes.next() {
             let arg1 = {
-                let (Some(n1), Some(n2), Some(n3), Some(n4)) = (bytes.next().copied(), bytes.next().copied(), bytes.next().copied(), bytes.next().copied()) else {
+                let (Some(n1), Some(n2), Some(n3), Some(n4)) = (bytes.next(), bytes.next(), bytes.next(), bytes.next()) else {
                     return Err(ProgramParseError::InsufficiantBytes);
                 };
                 u32::from_be_bytes([n1, n2, n3, n4])
             };
             let arg2 = {
-                let (Some(n1), Some(n2), Some(n3), Some(n4)) = (bytes.next().copied(), bytes.next().copied(), bytes.next().copied(), bytes.next().copied()) else {
+                let (Some(n1), Some(n2), Some(n3), Some(n4)) = (bytes.next(), bytes.next(), bytes.next(), bytes.next()) else {
                     return Err(ProgramParseError::InsufficiantBytes);
                 };
                 u32::from_be_bytes([n1, n2, n3, n4])
             };
             let arg3 = {
-                let (Some(n1), Some(n2), Some(n3), Some(n4)) = (bytes.next().copied(), bytes.next().copied(), bytes.next().copied(), bytes.next().copied()) else {
+                let (Some(n1), Some(n2), Some(n3), Some(n4)) = (bytes.next(), bytes.next(), bytes.next(), bytes.next()) else {
                     return Err(ProgramParseError::InsufficiantBytes);
                 };
                 u32::from_be_bytes([n1, n2, n3, n4])
@@ -333,17 +458,16 @@ impl TryFrom<&[u8]> for Program {
             code.push(ByteCode::try_from((instr, arg1, arg2, arg3)).map_err(ProgramParseError::ByteCodeError)?);
         }
 
-        Ok(Self { strings, code })
+        Ok(Self { constants, code })
     }
 }
 impl From<Program> for Vec<u8> {
     fn from(program: Program) -> Self {
         let mut bytes = vec![];
 
-        bytes.extend((program.strings.len() as u32).to_be_bytes());
-        for string in program.strings {
-            bytes.extend((string.len() as u32).to_be_bytes());
-            bytes.extend(string.chars().map(|c| c as u8));
+        bytes.extend((program.constants.len() as u32).to_be_bytes());
+        for constant in &program.constants {
+            constant.encode(&mut bytes);
         }
 
         for bytecode in program.code {
@@ -0,0 +1,102 @@
+//! A sparse, page-backed address space for `Load`/`Store`. Pages are
+//! allocated lazily on first write, so a program can address a large
+//! logical space (the full `u32` range) while only paying for the pages it
+//! actually touches.
+
+use crate::Address;
+use std::{collections::HashMap, error::Error, fmt::Display};
+
+pub const PAGE_SIZE: u32 = 4096;
+
+/// A `Load`/`Store` operand width, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Width {
+    B1 = 1,
+    B2 = 2,
+    B4 = 4,
+    B8 = 8,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidWidth(pub u32);
+impl TryFrom<u32> for Width {
+    type Error = InvalidWidth;
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::B1),
+            2 => Ok(Self::B2),
+            4 => Ok(Self::B4),
+            8 => Ok(Self::B8),
+            other => Err(InvalidWidth(other)),
+        }
+    }
+}
+impl From<Width> for u32 {
+    fn from(width: Width) -> Self {
+        width as u32
+    }
+}
+impl Width {
+    fn bytes(self) -> u32 {
+        self as u32
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryError {
+    /// `addr` is not a multiple of the access width.
+    Unaligned(Address, Width),
+    /// A read touched a page that was never written.
+    UnmappedRead(Address),
+}
+impl Display for MemoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemoryError::Unaligned(addr, width) => {
+                write!(f, "unaligned access at 0x{addr:x} for width {}", width.bytes())
+            }
+            MemoryError::UnmappedRead(addr) => write!(f, "read from unmapped address 0x{addr:x}"),
+        }
+    }
+}
+impl Error for MemoryError {}
+
+/// A page-backed, sparsely-allocated memory.
+#[derive(Default)]
+pub struct Memory {
+    pages: HashMap<u32, Box<[u8; PAGE_SIZE as usize]>>,
+}
+impl Memory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn locate(addr: Address, width: Width) -> Result<(u32, usize), MemoryError> {
+        if !addr.is_multiple_of(width.bytes()) {
+            return Err(MemoryError::Unaligned(addr, width));
+        }
+        Ok((addr / PAGE_SIZE, (addr % PAGE_SIZE) as usize))
+    }
+
+    pub fn read(&self, addr: Address, width: Width) -> Result<u64, MemoryError> {
+        let (page, offset) = Self::locate(addr, width)?;
+        let page = self
+            .pages
+            .get(&page)
+            .ok_or(MemoryError::UnmappedRead(addr))?;
+        let mut buf = [0u8; 8];
+        buf[..width.bytes() as usize].copy_from_slice(&page[offset..offset + width.bytes() as usize]);
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    pub fn write(&mut self, addr: Address, width: Width, value: u64) -> Result<(), MemoryError> {
+        let (page, offset) = Self::locate(addr, width)?;
+        let page = self
+            .pages
+            .entry(page)
+            .or_insert_with(|| Box::new([0; PAGE_SIZE as usize]));
+        let bytes = value.to_le_bytes();
+        page[offset..offset + width.bytes() as usize].copy_from_slice(&bytes[..width.bytes() as usize]);
+        Ok(())
+    }
+}
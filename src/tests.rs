@@ -0,0 +1,431 @@
+use crate::*;
+use crate::asm::{assemble, disassemble, AsmError};
+use crate::memory::{Memory, MemoryError, Width};
+use crate::vm::{Fault, Status, Vm};
+
+#[test]
+fn bytecode_roundtrip() {
+    let cases = [
+        ByteCode::None,
+        ByteCode::Halt,
+        ByteCode::Jump { addr: Locator::Address(5) },
+        ByteCode::JumpIf { cond: 1, addr: Locator::FromRegister(2) },
+        ByteCode::String { dst: 0, addr: 3 },
+        ByteCode::Int { dst: 0, value: 42 },
+        ByteCode::Float { dst: 0, value: 1.5 },
+        ByteCode::Bool { dst: 0, value: true },
+        ByteCode::Move { dst: 0, src: 1 },
+        ByteCode::Field { dst: 0, src: 1, field: 2 },
+        ByteCode::Call { addr: Locator::Address(4), args: 2, dst: 0 },
+        ByteCode::Ecall { id: 7, args: 1, dst: 0 },
+        ByteCode::Trap { code: 9 },
+        ByteCode::Load { dst: 0, addr: Locator::Address(8), size: Width::B4 },
+        ByteCode::Store { src: 0, addr: Locator::FromRegister(1), size: Width::B8 },
+        ByteCode::Const { dst: 0, index: 3 },
+        ByteCode::Tick { dst: 0 },
+        ByteCode::Binary { op: BinaryOperation::Add, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::Sub, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::Div, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::Mul, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::Mod, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::Eq, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::Ne, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::Lt, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::Le, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::Gt, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::Ge, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::And, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::Or, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::BitAnd, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::BitOr, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::BitXor, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::Shl, dst: 0, left: 1, right: 2 },
+        ByteCode::Binary { op: BinaryOperation::Shr, dst: 0, left: 1, right: 2 },
+        ByteCode::Unary { op: UnaryOperation::Neg, dst: 0, right: 1 },
+        ByteCode::Unary { op: UnaryOperation::Not, dst: 0, right: 1 },
+        ByteCode::Unary { op: UnaryOperation::BitNot, dst: 0, right: 1 },
+    ];
+    for case in cases {
+        let bytes: Bytes = case.into();
+        assert_eq!(ByteCode::try_from(bytes).unwrap(), case);
+    }
+}
+
+#[test]
+fn program_roundtrip() {
+    let program = Program {
+        constants: vec![
+            Constant::Text("hello".to_string()),
+            Constant::List(vec![Constant::I32(-1), Constant::Bool(true)]),
+            Constant::Record(vec![("k".to_string(), Constant::U64(9))]),
+        ],
+        code: vec![
+            ByteCode::Int { dst: 0, value: 1 },
+            ByteCode::Const { dst: 1, index: 0 },
+            ByteCode::Halt,
+        ],
+    };
+    let bytes: Vec<u8> = program.clone().into();
+    assert_eq!(Program::try_from(bytes.as_slice()).unwrap(), program);
+}
+
+#[test]
+fn vm_runs_arithmetic() {
+    let program = Program {
+        constants: vec![],
+        code: vec![
+            ByteCode::Int { dst: 0, value: 2 },
+            ByteCode::Int { dst: 1, value: 3 },
+            ByteCode::Binary { op: BinaryOperation::Add, dst: 2, left: 0, right: 1 },
+            ByteCode::Halt,
+        ],
+    };
+    let mut vm = Vm::new(&program);
+    vm.run().unwrap();
+    assert_eq!(vm.register(2).unwrap(), 5);
+}
+
+#[test]
+fn vm_reports_division_by_zero() {
+    let program = Program {
+        constants: vec![],
+        code: vec![
+            ByteCode::Int { dst: 0, value: 1 },
+            ByteCode::Int { dst: 1, value: 0 },
+            ByteCode::Binary { op: BinaryOperation::Div, dst: 2, left: 0, right: 1 },
+            ByteCode::Halt,
+        ],
+    };
+    let mut vm = Vm::new(&program);
+    assert_eq!(vm.run(), Err(vm::RuntimeError::DivisionByZero));
+}
+
+#[test]
+fn vm_jumps() {
+    let program = Program {
+        constants: vec![],
+        code: vec![
+            ByteCode::Jump { addr: Locator::Address(2) },
+            ByteCode::Int { dst: 0, value: 999 },
+            ByteCode::Int { dst: 0, value: 1 },
+            ByteCode::Halt,
+        ],
+    };
+    let mut vm = Vm::new(&program);
+    vm.run().unwrap();
+    assert_eq!(vm.register(0).unwrap(), 1);
+}
+
+#[test]
+fn compact_roundtrip() {
+    let program = Program {
+        constants: vec![Constant::Text("hi".to_string()), Constant::I64(-7)],
+        code: vec![
+            ByteCode::Int { dst: 0, value: 2 },
+            ByteCode::Int { dst: 1, value: 3 },
+            ByteCode::Binary { op: BinaryOperation::Add, dst: 2, left: 0, right: 1 },
+            ByteCode::Jump { addr: Locator::Address(0) },
+            ByteCode::JumpIf { cond: 2, addr: Locator::FromRegister(3) },
+            ByteCode::Call { addr: Locator::Address(1), args: 2, dst: 0 },
+            ByteCode::Ecall { id: 5, args: 0, dst: 1 },
+            ByteCode::Trap { code: 2 },
+            ByteCode::Load { dst: 0, addr: Locator::Address(4096), size: Width::B2 },
+            ByteCode::Store { src: 0, addr: Locator::FromRegister(1), size: Width::B1 },
+            ByteCode::Float { dst: 0, value: 2.5 },
+            ByteCode::Const { dst: 3, index: 1 },
+            ByteCode::Tick { dst: 4 },
+            ByteCode::Halt,
+        ],
+    };
+    let bytes = program.encode_compact();
+    assert_eq!(Program::decode_compact(&bytes).unwrap(), program);
+}
+
+#[test]
+fn compact_shrinks_low_register_programs() {
+    let program = Program {
+        constants: vec![],
+        code: vec![ByteCode::Move { dst: 0, src: 1 }],
+    };
+    assert!(program.encode_compact().len() < Vec::<u8>::from(program).len());
+}
+
+#[test]
+fn compact_shrinks_small_int_and_float_immediates() {
+    let program = Program {
+        constants: vec![],
+        code: vec![
+            ByteCode::Int { dst: 0, value: 0 },
+            ByteCode::Float { dst: 0, value: 0.0 },
+        ],
+    };
+    let compact = program.encode_compact();
+    assert!(compact.len() < Vec::<u8>::from(program.clone()).len());
+    assert_eq!(Program::decode_compact(&compact).unwrap(), program);
+}
+
+#[test]
+fn asm_roundtrip() {
+    let program = Program {
+        constants: vec![
+            Constant::Text("hello".to_string()),
+            Constant::List(vec![Constant::Unit, Constant::Float(1.5)]),
+            Constant::Record(vec![("name".to_string(), Constant::Bytes(vec![1, 2, 3]))]),
+        ],
+        code: vec![
+            ByteCode::Int { dst: 0, value: 2 },
+            ByteCode::Int { dst: 1, value: 3 },
+            ByteCode::Binary { op: BinaryOperation::Add, dst: 2, left: 0, right: 1 },
+            ByteCode::JumpIf { cond: 2, addr: Locator::Address(0) },
+            ByteCode::Call { addr: Locator::FromRegister(1), args: 2, dst: 0 },
+            ByteCode::Unary { op: UnaryOperation::Neg, dst: 0, right: 1 },
+            ByteCode::Ecall { id: 3, args: 0, dst: 1 },
+            ByteCode::Trap { code: 9 },
+            ByteCode::Load { dst: 0, addr: Locator::Address(16), size: Width::B4 },
+            ByteCode::Store { src: 0, addr: Locator::FromRegister(2), size: Width::B8 },
+            ByteCode::Const { dst: 0, index: 2 },
+            ByteCode::Tick { dst: 3 },
+            ByteCode::Halt,
+        ],
+    };
+    let text = disassemble(&program);
+    assert_eq!(assemble(&text).unwrap(), program);
+}
+
+#[test]
+fn asm_roundtrips_no_arg_constant_at_end_of_list_or_record() {
+    let program = Program {
+        constants: vec![
+            Constant::List(vec![Constant::I32(-1), Constant::Unit]),
+            Constant::Record(vec![("k".to_string(), Constant::Unit)]),
+        ],
+        code: vec![ByteCode::Halt],
+    };
+    let text = disassemble(&program);
+    assert_eq!(assemble(&text).unwrap(), program);
+}
+
+#[test]
+fn asm_roundtrips_text_with_quotes_and_backslashes() {
+    let program = Program {
+        constants: vec![
+            Constant::Text("a\\b\"c".to_string()),
+            Constant::Record(vec![("k\"e\\y".to_string(), Constant::Unit)]),
+        ],
+        code: vec![ByteCode::Halt],
+    };
+    let text = disassemble(&program);
+    assert_eq!(assemble(&text).unwrap(), program);
+}
+
+#[test]
+fn asm_rejects_unknown_mnemonic() {
+    assert_eq!(
+        assemble("bogus r0, r1"),
+        Err(AsmError::UnknownMnemonic("bogus".to_string()))
+    );
+}
+
+#[test]
+fn vm_ecall_invokes_host() {
+    let program = Program {
+        constants: vec![],
+        code: vec![
+            ByteCode::Int { dst: 0, value: 10 },
+            ByteCode::Ecall { id: 1, args: 0, dst: 1 },
+            ByteCode::Halt,
+        ],
+    };
+    let mut vm = Vm::new(&program);
+    vm.register_host(1, |vm, args, dst| {
+        let value = vm.register(args).map_err(|_| Fault::UnknownEcall(1))?;
+        vm.set_register(dst, value * 2).map_err(|_| Fault::UnknownEcall(1))?;
+        Ok(())
+    });
+    assert_eq!(vm.run(), Ok(Status::Halted));
+    assert_eq!(vm.register(1).unwrap(), 20);
+}
+
+#[test]
+fn vm_unknown_ecall_traps() {
+    let program = Program {
+        constants: vec![],
+        code: vec![ByteCode::Ecall { id: 42, args: 0, dst: 0 }, ByteCode::Halt],
+    };
+    let mut vm = Vm::new(&program);
+    assert_eq!(vm.run(), Ok(Status::Trapped(Fault::UnknownEcall(42))));
+}
+
+#[test]
+fn vm_explicit_trap() {
+    let program = Program {
+        constants: vec![],
+        code: vec![ByteCode::Trap { code: 5 }],
+    };
+    let mut vm = Vm::new(&program);
+    assert_eq!(vm.run(), Ok(Status::Trapped(Fault::Explicit(5))));
+}
+
+#[test]
+fn vm_store_then_load_roundtrips() {
+    let program = Program {
+        constants: vec![],
+        code: vec![
+            ByteCode::Int { dst: 0, value: 4 },
+            ByteCode::Int { dst: 1, value: 0xdead_beef },
+            ByteCode::Store { src: 1, addr: Locator::FromRegister(0), size: Width::B4 },
+            ByteCode::Load { dst: 2, addr: Locator::FromRegister(0), size: Width::B4 },
+            ByteCode::Halt,
+        ],
+    };
+    let mut vm = Vm::new(&program);
+    vm.run().unwrap();
+    assert_eq!(vm.register(2).unwrap(), 0xdead_beef);
+}
+
+#[test]
+fn vm_unmapped_read_faults() {
+    let program = Program {
+        constants: vec![],
+        code: vec![
+            ByteCode::Int { dst: 0, value: 0 },
+            ByteCode::Load { dst: 1, addr: Locator::FromRegister(0), size: Width::B4 },
+            ByteCode::Halt,
+        ],
+    };
+    let mut vm = Vm::new(&program);
+    assert_eq!(
+        vm.run(),
+        Err(vm::RuntimeError::Memory(MemoryError::UnmappedRead(0)))
+    );
+}
+
+#[test]
+fn memory_rejects_unaligned_access() {
+    let mut memory = Memory::new();
+    assert_eq!(
+        memory.write(1, Width::B4, 42),
+        Err(MemoryError::Unaligned(1, Width::B4))
+    );
+}
+
+#[test]
+fn memory_write_read_across_pages() {
+    let mut memory = Memory::new();
+    memory.write(0, Width::B1, 1).unwrap();
+    memory.write(crate::memory::PAGE_SIZE, Width::B1, 2).unwrap();
+    assert_eq!(memory.read(0, Width::B1).unwrap(), 1);
+    assert_eq!(memory.read(crate::memory::PAGE_SIZE, Width::B1).unwrap(), 2);
+}
+
+#[test]
+fn vm_const_loads_scalar_and_placeholder_handle() {
+    let program = Program {
+        constants: vec![Constant::I32(-5), Constant::Text("hi".to_string())],
+        code: vec![
+            ByteCode::Const { dst: 0, index: 0 },
+            ByteCode::Const { dst: 1, index: 1 },
+            ByteCode::Halt,
+        ],
+    };
+    let mut vm = Vm::new(&program);
+    vm.run().unwrap();
+    assert_eq!(vm.register(0).unwrap() as i64, -5);
+    assert_eq!(vm.register(1).unwrap(), 1);
+}
+
+#[test]
+fn vm_const_out_of_range_is_bad_constant() {
+    let program = Program {
+        constants: vec![],
+        code: vec![ByteCode::Const { dst: 0, index: 3 }],
+    };
+    let mut vm = Vm::new(&program);
+    assert_eq!(vm.run(), Err(vm::RuntimeError::BadConstant(3)));
+}
+
+#[test]
+fn vm_comparison_feeds_jumpif() {
+    let program = Program {
+        constants: vec![],
+        code: vec![
+            ByteCode::Int { dst: 0, value: 3 },
+            ByteCode::Int { dst: 1, value: 5 },
+            ByteCode::Binary { op: BinaryOperation::Lt, dst: 2, left: 0, right: 1 },
+            ByteCode::JumpIf { cond: 2, addr: Locator::Address(5) },
+            ByteCode::Int { dst: 3, value: 0 },
+            ByteCode::Int { dst: 3, value: 1 },
+            ByteCode::Halt,
+        ],
+    };
+    let mut vm = Vm::new(&program);
+    vm.run().unwrap();
+    assert_eq!(vm.register(3).unwrap(), 1);
+}
+
+#[test]
+fn vm_bitwise_and_shift_ops() {
+    let program = Program {
+        constants: vec![],
+        code: vec![
+            ByteCode::Int { dst: 0, value: 0b1010 },
+            ByteCode::Int { dst: 1, value: 0b0110 },
+            ByteCode::Binary { op: BinaryOperation::BitAnd, dst: 2, left: 0, right: 1 },
+            ByteCode::Binary { op: BinaryOperation::BitOr, dst: 3, left: 0, right: 1 },
+            ByteCode::Binary { op: BinaryOperation::BitXor, dst: 4, left: 0, right: 1 },
+            ByteCode::Int { dst: 5, value: 2 },
+            ByteCode::Binary { op: BinaryOperation::Shl, dst: 6, left: 0, right: 5 },
+            ByteCode::Unary { op: UnaryOperation::BitNot, dst: 7, right: 0 },
+            ByteCode::Halt,
+        ],
+    };
+    let mut vm = Vm::new(&program);
+    vm.run().unwrap();
+    assert_eq!(vm.register(2).unwrap(), 0b0010);
+    assert_eq!(vm.register(3).unwrap(), 0b1110);
+    assert_eq!(vm.register(4).unwrap(), 0b1100);
+    assert_eq!(vm.register(6).unwrap(), 0b101000);
+    assert_eq!(vm.register(7).unwrap(), !0b1010u64);
+}
+
+#[test]
+fn vm_tick_reads_elapsed_steps() {
+    let program = Program {
+        constants: vec![],
+        code: vec![
+            ByteCode::Int { dst: 0, value: 0 },
+            ByteCode::Int { dst: 0, value: 0 },
+            ByteCode::Tick { dst: 1 },
+            ByteCode::Halt,
+        ],
+    };
+    let mut vm = Vm::new(&program);
+    vm.run().unwrap();
+    assert_eq!(vm.register(1).unwrap(), 3);
+}
+
+#[test]
+fn vm_run_with_budget_pauses_and_resumes() {
+    let program = Program {
+        constants: vec![],
+        code: vec![
+            ByteCode::Int { dst: 0, value: 1 },
+            ByteCode::Int { dst: 1, value: 2 },
+            ByteCode::Binary { op: BinaryOperation::Add, dst: 2, left: 0, right: 1 },
+            ByteCode::Halt,
+        ],
+    };
+    let mut vm = Vm::new(&program);
+    assert_eq!(vm.run_with_budget(2).unwrap(), Status::OutOfFuel);
+    assert_eq!(vm.pc(), 2);
+    assert_eq!(vm.register(2).unwrap(), 0);
+    assert_eq!(vm.run_with_budget(10).unwrap(), Status::Halted);
+    assert_eq!(vm.register(2).unwrap(), 3);
+}
+
+#[test]
+fn vm_step_halted_status() {
+    let program = Program { constants: vec![], code: vec![ByteCode::Halt] };
+    let mut vm = Vm::new(&program);
+    assert_eq!(vm.step().unwrap(), Status::Halted);
+}
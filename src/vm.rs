@@ -0,0 +1,390 @@
+use crate::{
+    constant::Constant,
+    memory::{Memory, MemoryError},
+    BinaryOperation, ByteCode, Locator, Program, Register, UnaryOperation,
+};
+use std::{collections::HashMap, error::Error, fmt::Display};
+
+/// Number of registers available in a single call frame.
+const REGISTER_COUNT: usize = 256;
+/// Maximum number of nested calls before a program is considered runaway.
+const MAX_CALL_DEPTH: usize = 1024;
+
+/// A single call frame: its own register window and where to resume the
+/// caller once the frame is popped.
+struct Frame {
+    registers: Box<[u64; REGISTER_COUNT]>,
+    return_pc: usize,
+    dst: Register,
+}
+impl Frame {
+    fn new(return_pc: usize, dst: Register) -> Self {
+        Self {
+            registers: Box::new([0; REGISTER_COUNT]),
+            return_pc,
+            dst,
+        }
+    }
+}
+
+/// Faults that can occur while stepping a [`Program`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuntimeError {
+    DivisionByZero,
+    BadRegister(Register),
+    OutOfRangeJump(u32),
+    StackOverflow,
+    Memory(MemoryError),
+    BadConstant(u32),
+}
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuntimeError::DivisionByZero => write!(f, "division by zero"),
+            RuntimeError::BadRegister(reg) => write!(f, "bad register r{reg}"),
+            RuntimeError::OutOfRangeJump(addr) => write!(f, "jump out of range: {addr}"),
+            RuntimeError::StackOverflow => write!(f, "call stack overflow"),
+            RuntimeError::Memory(err) => err.fmt(f),
+            RuntimeError::BadConstant(index) => write!(f, "no constant at index {index}"),
+        }
+    }
+}
+impl From<MemoryError> for RuntimeError {
+    fn from(value: MemoryError) -> Self {
+        RuntimeError::Memory(value)
+    }
+}
+impl Error for RuntimeError {}
+
+/// A recoverable fault raised by `Ecall`/`Trap`. Unlike [`RuntimeError`],
+/// a host is expected to inspect these and decide whether to resume the
+/// `Vm` or abort it, rather than treating them as unconditionally fatal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// `Ecall` named an `id` with no registered host handler.
+    UnknownEcall(u32),
+    /// The program executed an explicit `Trap` instruction.
+    Explicit(u32),
+}
+impl Display for Fault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fault::UnknownEcall(id) => write!(f, "unknown ecall id {id}"),
+            Fault::Explicit(code) => write!(f, "trap {code}"),
+        }
+    }
+}
+impl Error for Fault {}
+
+/// A function exposed to running programs through `Ecall`. Receives the
+/// `Vm` so it can read the argument registers starting at `args` and write
+/// a result into `dst`; returns `Err` to raise a [`Fault`] back into the
+/// interpreter instead of completing normally.
+pub type HostFn<'p> = Box<dyn FnMut(&mut Vm<'p>, Register, Register) -> Result<(), Fault> + 'p>;
+
+/// Why a [`Vm`] stopped stepping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Halted,
+    Running,
+    /// A recoverable fault was raised; the host may inspect it and call
+    /// [`Vm::step`]/[`Vm::run`] again to resume, or give up on the program.
+    Trapped(Fault),
+    /// [`Vm::run_with_budget`] exhausted its step count before the program
+    /// halted or faulted. The `Vm` itself still holds the paused PC and
+    /// register state, so the host may inspect it (see [`Vm::pc`] and
+    /// [`Vm::register`]) and call `run_with_budget` again to resume.
+    OutOfFuel,
+}
+
+/// A register-machine interpreter over a [`Program`].
+///
+/// Registers hold raw 64-bit words; each instruction reinterprets the bits
+/// it reads according to its own operand types (integer, float, boolean or
+/// constant-pool index).
+pub struct Vm<'p> {
+    program: &'p Program,
+    pc: usize,
+    frames: Vec<Frame>,
+    hosts: HashMap<u32, HostFn<'p>>,
+    memory: Memory,
+    /// Elapsed instruction count, wrapping on overflow. Readable from a
+    /// running program through `ByteCode::Tick`.
+    ticks: u64,
+}
+impl<'p> Vm<'p> {
+    pub fn new(program: &'p Program) -> Self {
+        Self {
+            program,
+            pc: 0,
+            frames: vec![Frame::new(0, 0)],
+            hosts: HashMap::new(),
+            memory: Memory::new(),
+            ticks: 0,
+        }
+    }
+
+    /// Expose a host function to `Ecall` instructions that name `id`.
+    pub fn register_host(
+        &mut self,
+        id: u32,
+        handler: impl FnMut(&mut Self, Register, Register) -> Result<(), Fault> + 'p,
+    ) {
+        self.hosts.insert(id, Box::new(handler));
+    }
+
+    /// The index of the next instruction to execute.
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// Run until the program halts or a fault is raised.
+    pub fn run(&mut self) -> Result<Status, RuntimeError> {
+        loop {
+            match self.step()? {
+                Status::Running => continue,
+                status => return Ok(status),
+            }
+        }
+    }
+
+    /// Run for at most `steps` instructions, yielding [`Status::OutOfFuel`]
+    /// if the program is still running when the budget is exhausted. The
+    /// `Vm` remains paused at the PC it reached, so calling this again
+    /// (with a refilled budget) resumes exactly where it left off.
+    pub fn run_with_budget(&mut self, steps: u64) -> Result<Status, RuntimeError> {
+        for _ in 0..steps {
+            match self.step()? {
+                Status::Running => continue,
+                status => return Ok(status),
+            }
+        }
+        Ok(Status::OutOfFuel)
+    }
+
+    fn frame(&self) -> &Frame {
+        self.frames.last().expect("vm always has at least one frame")
+    }
+    fn frame_mut(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("vm always has at least one frame")
+    }
+
+    pub fn register(&self, reg: Register) -> Result<u64, RuntimeError> {
+        self.frame()
+            .registers
+            .get(reg as usize)
+            .copied()
+            .ok_or(RuntimeError::BadRegister(reg))
+    }
+    pub fn set_register(&mut self, reg: Register, value: u64) -> Result<(), RuntimeError> {
+        *self
+            .frame_mut()
+            .registers
+            .get_mut(reg as usize)
+            .ok_or(RuntimeError::BadRegister(reg))? = value;
+        Ok(())
+    }
+
+    fn resolve(&self, locator: Locator) -> Result<u32, RuntimeError> {
+        match locator {
+            Locator::Address(addr) => Ok(addr),
+            Locator::FromRegister(reg) => Ok(self.register(reg)? as u32),
+        }
+    }
+
+    fn jump(&mut self, addr: u32) -> Result<(), RuntimeError> {
+        let addr = addr as usize;
+        if addr >= self.program.code.len() {
+            return Err(RuntimeError::OutOfRangeJump(addr as u32));
+        }
+        self.pc = addr;
+        Ok(())
+    }
+
+    /// Execute a single [`ByteCode`], advancing the program counter.
+    pub fn step(&mut self) -> Result<Status, RuntimeError> {
+        let Some(&instr) = self.program.code.get(self.pc) else {
+            return Ok(Status::Halted);
+        };
+        let mut next_pc = self.pc + 1;
+        self.ticks = self.ticks.wrapping_add(1);
+
+        match instr {
+            ByteCode::None => {}
+            ByteCode::Halt => {
+                if self.frames.len() > 1 {
+                    let frame = self.frames.pop().expect("checked len > 1");
+                    // By convention the callee leaves its result in r0;
+                    // there is no dedicated `Return` opcode yet.
+                    let result = frame.registers[0];
+                    next_pc = frame.return_pc;
+                    self.set_register(frame.dst, result)?;
+                } else {
+                    return Ok(Status::Halted);
+                }
+            }
+            ByteCode::Jump { addr } => {
+                let addr = self.resolve(addr)?;
+                self.jump(addr)?;
+                next_pc = self.pc;
+            }
+            ByteCode::JumpIf { cond, addr } => {
+                if self.register(cond)? != 0 {
+                    let addr = self.resolve(addr)?;
+                    self.jump(addr)?;
+                    next_pc = self.pc;
+                }
+            }
+            ByteCode::String { dst, addr } => self.set_register(dst, addr as u64)?,
+            ByteCode::Int { dst, value } => self.set_register(dst, value)?,
+            ByteCode::Float { dst, value } => self.set_register(dst, value.to_bits())?,
+            ByteCode::Bool { dst, value } => self.set_register(dst, value as u64)?,
+            ByteCode::Move { dst, src } => {
+                let value = self.register(src)?;
+                self.set_register(dst, value)?;
+            }
+            ByteCode::Field { dst, src, field } => {
+                // No heap/object model exists yet; treat `field` as an
+                // offset from the base value until composite values land.
+                let base = self.register(src)?;
+                self.set_register(dst, base.wrapping_add(field as u64))?;
+            }
+            ByteCode::Call { addr, args, dst } => {
+                if self.frames.len() >= MAX_CALL_DEPTH {
+                    return Err(RuntimeError::StackOverflow);
+                }
+                let target = self.resolve(addr)?;
+                if args as usize > REGISTER_COUNT {
+                    return Err(RuntimeError::BadRegister(args));
+                }
+                let mut arg_values = Vec::with_capacity(args as usize);
+                for reg in 0..args {
+                    arg_values.push(self.register(reg)?);
+                }
+                self.frames.push(Frame::new(next_pc, dst));
+                for (reg, value) in arg_values.into_iter().enumerate() {
+                    self.set_register(reg as Register, value)?;
+                }
+                self.jump(target)?;
+                next_pc = self.pc;
+            }
+            ByteCode::Ecall { id, args, dst } => {
+                let fault = match self.hosts.remove(&id) {
+                    Some(mut handler) => {
+                        let result = handler(self, args, dst);
+                        self.hosts.insert(id, handler);
+                        result.err()
+                    }
+                    None => Some(Fault::UnknownEcall(id)),
+                };
+                self.pc = next_pc;
+                return match fault {
+                    Some(fault) => Ok(Status::Trapped(fault)),
+                    None => Ok(Status::Running),
+                };
+            }
+            ByteCode::Trap { code } => {
+                self.pc = next_pc;
+                return Ok(Status::Trapped(Fault::Explicit(code)));
+            }
+            ByteCode::Load { dst, addr, size } => {
+                let addr = self.resolve(addr)?;
+                let value = self.memory.read(addr, size)?;
+                self.set_register(dst, value)?;
+            }
+            ByteCode::Store { src, addr, size } => {
+                let addr = self.resolve(addr)?;
+                let value = self.register(src)?;
+                self.memory.write(addr, size, value)?;
+            }
+            ByteCode::Const { dst, index } => {
+                let value = self.constant_value(index)?;
+                self.set_register(dst, value)?;
+            }
+            ByteCode::Tick { dst } => self.set_register(dst, self.ticks)?,
+            ByteCode::Binary { op, dst, left, right } => {
+                let left = self.register(left)?;
+                let right = self.register(right)?;
+                let value = Self::binary(op, left, right)?;
+                self.set_register(dst, value)?;
+            }
+            ByteCode::Unary { op, dst, right } => {
+                let right = self.register(right)?;
+                let value = Self::unary(op, right);
+                self.set_register(dst, value)?;
+            }
+        }
+
+        self.pc = next_pc;
+        Ok(Status::Running)
+    }
+
+    /// Materialize the constant at `index` as a register value. Scalars are
+    /// reduced to their bit pattern; there is no heap/object model yet, so
+    /// `Text`/`Bytes`/`List`/`Record` constants resolve to their pool index
+    /// as a placeholder handle, the same convention `Field` uses for offsets.
+    fn constant_value(&self, index: u32) -> Result<u64, RuntimeError> {
+        let constant = self
+            .program
+            .constants
+            .get(index as usize)
+            .ok_or(RuntimeError::BadConstant(index))?;
+        Ok(match constant {
+            Constant::Unit => 0,
+            Constant::Bool(value) => *value as u64,
+            Constant::I8(value) => *value as i64 as u64,
+            Constant::I16(value) => *value as i64 as u64,
+            Constant::I32(value) => *value as i64 as u64,
+            Constant::I64(value) => *value as u64,
+            Constant::U8(value) => *value as u64,
+            Constant::U16(value) => *value as u64,
+            Constant::U32(value) => *value as u64,
+            Constant::U64(value) => *value,
+            Constant::Float(value) => value.to_bits(),
+            Constant::Text(_) | Constant::Bytes(_) | Constant::List(_) | Constant::Record(_) => {
+                index as u64
+            }
+        })
+    }
+
+    fn binary(op: BinaryOperation, left: u64, right: u64) -> Result<u64, RuntimeError> {
+        let (l, r) = (left as i64, right as i64);
+        Ok(match op {
+            BinaryOperation::Add => l.wrapping_add(r) as u64,
+            BinaryOperation::Sub => l.wrapping_sub(r) as u64,
+            BinaryOperation::Mul => l.wrapping_mul(r) as u64,
+            BinaryOperation::Div => {
+                if r == 0 {
+                    return Err(RuntimeError::DivisionByZero);
+                }
+                l.wrapping_div(r) as u64
+            }
+            BinaryOperation::Mod => {
+                if r == 0 {
+                    return Err(RuntimeError::DivisionByZero);
+                }
+                l.wrapping_rem(r) as u64
+            }
+            BinaryOperation::Eq => (l == r) as u64,
+            BinaryOperation::Ne => (l != r) as u64,
+            BinaryOperation::Lt => (l < r) as u64,
+            BinaryOperation::Le => (l <= r) as u64,
+            BinaryOperation::Gt => (l > r) as u64,
+            BinaryOperation::Ge => (l >= r) as u64,
+            BinaryOperation::And => (l != 0 && r != 0) as u64,
+            BinaryOperation::Or => (l != 0 || r != 0) as u64,
+            BinaryOperation::BitAnd => left & right,
+            BinaryOperation::BitOr => left | right,
+            BinaryOperation::BitXor => left ^ right,
+            BinaryOperation::Shl => left.wrapping_shl(right as u32),
+            BinaryOperation::Shr => left.wrapping_shr(right as u32),
+        })
+    }
+    fn unary(op: UnaryOperation, right: u64) -> u64 {
+        match op {
+            UnaryOperation::Neg => (right as i64).wrapping_neg() as u64,
+            UnaryOperation::Not => (right == 0) as u64,
+            UnaryOperation::BitNot => !right,
+        }
+    }
+}